@@ -0,0 +1,79 @@
+//! A library for mocking HTTP requests in tests, inspired by Ruby's WebMock.
+//!
+//! A single background server is started lazily on first use and listens on
+//! [`SERVER_ADDRESS`]. Tests register mocks with [`mock`] and the server
+//! matches incoming requests against them in registration order, falling
+//! back to a `501 Not Implemented` response when nothing matches.
+//!
+//! ```no_run
+//! use mockito::mock;
+//!
+//! mock("GET", "/hello").with_body("world").create();
+//! ```
+//!
+//! For tests that run in parallel, use [`Server::new`] to spin up an
+//! isolated server on its own ephemeral port instead of sharing the global
+//! one:
+//!
+//! ```no_run
+//! use mockito::Server;
+//!
+//! let server = Server::new();
+//! server.mock("GET", "/hello").with_body("world").create();
+//!
+//! let url = server.url();
+//! ```
+
+mod matcher;
+mod mock;
+mod server;
+
+pub use crate::matcher::Matcher;
+pub use crate::mock::Mock;
+pub use crate::server::{Server, ServerGuard};
+
+use lazy_static::lazy_static;
+use std::sync::{Arc, Mutex, Once};
+
+/// The address the global mock server listens on.
+pub const SERVER_ADDRESS: &str = "127.0.0.1:1234";
+
+lazy_static! {
+    static ref STATE: Arc<Mutex<Vec<Mock>>> = Arc::new(Mutex::new(Vec::new()));
+}
+
+static START: Once = Once::new();
+
+fn start_server() {
+    START.call_once(|| {
+        server::start(SERVER_ADDRESS, STATE.clone());
+    });
+}
+
+/// Starts a mock for the given HTTP method and path, returning a builder that
+/// can be refined with `match_header`/`match_body`/`match_query`/`with_*`
+/// calls before `create()` registers it.
+///
+/// `path` accepts an exact string or a `Matcher` (e.g. a `Regex`), so a
+/// single mock can serve a family of paths such as `/users/123` and
+/// `/users/456`.
+///
+/// This registers the mock against the global server on [`SERVER_ADDRESS`].
+/// For isolated, parallel-safe tests, use [`Server::new`] and
+/// `ServerGuard::mock` instead.
+pub fn mock<P: Into<Matcher>>(method: &str, path: P) -> Mock {
+    start_server();
+    Mock::new(method, path, STATE.clone())
+}
+
+/// Removes all mocks registered on the global server and zeroes their hit
+/// counters.
+pub fn reset() {
+    let mut state = STATE.lock().unwrap();
+
+    for mock in state.iter() {
+        mock.reset_hits();
+    }
+
+    state.clear();
+}
@@ -0,0 +1,56 @@
+use regex::Regex;
+
+/// Describes how a mock compares a piece of an incoming request (body, path,
+/// query string, ...) against an expected value.
+#[derive(Clone, Debug)]
+pub enum Matcher {
+    /// Matches only when the value is exactly equal to the given string.
+    Exact(String),
+    /// Matches when the value contains the given substring.
+    Substring(String),
+    /// Matches when the value satisfies the given regular expression.
+    Regex(Regex),
+    /// Matches when the value, parsed as a `&`-separated list of
+    /// `key=value` query parameters, contains this key/value pair. Order is
+    /// not significant.
+    UrlEncoded(String, String),
+    /// Matches when every sub-matcher matches the same value. Used to
+    /// combine several `UrlEncoded` matchers into a single query matcher.
+    AllOf(Vec<Matcher>),
+}
+
+impl Matcher {
+    pub(crate) fn matches(&self, value: &str) -> bool {
+        match self {
+            Matcher::Exact(expected) => value == expected,
+            Matcher::Substring(expected) => value.contains(expected.as_str()),
+            Matcher::Regex(regex) => regex.is_match(value),
+            Matcher::UrlEncoded(key, expected_value) => value.split('&').any(|pair| {
+                let mut parts = pair.splitn(2, '=');
+                let pair_key = parts.next().unwrap_or("");
+                let pair_value = parts.next().unwrap_or("");
+
+                pair_key == key && pair_value == expected_value
+            }),
+            Matcher::AllOf(matchers) => matchers.iter().all(|matcher| matcher.matches(value)),
+        }
+    }
+}
+
+impl From<&str> for Matcher {
+    fn from(value: &str) -> Matcher {
+        Matcher::Exact(value.to_owned())
+    }
+}
+
+impl From<String> for Matcher {
+    fn from(value: String) -> Matcher {
+        Matcher::Exact(value)
+    }
+}
+
+impl From<Regex> for Matcher {
+    fn from(regex: Regex) -> Matcher {
+        Matcher::Regex(regex)
+    }
+}
@@ -0,0 +1,238 @@
+use crate::matcher::Matcher;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A builder for an HTTP mock, returned by `mock(...)` or
+/// `ServerGuard::mock(...)`.
+///
+/// Mocks are inert until `create()` (or `create_for()`) is called, at which
+/// point they start being matched against incoming requests on the server
+/// instance that created them.
+#[derive(Clone)]
+pub struct Mock {
+    pub(crate) id: usize,
+    pub(crate) state: Arc<Mutex<Vec<Mock>>>,
+    pub(crate) method: String,
+    pub(crate) path_matcher: Matcher,
+    pub(crate) query_matcher: Option<Matcher>,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body_matcher: Option<Matcher>,
+    pub(crate) response_status: u16,
+    pub(crate) response_reason: Option<String>,
+    pub(crate) response_headers: Vec<(String, String)>,
+    pub(crate) response_body: String,
+    pub(crate) response_delay: Option<Duration>,
+    pub(crate) hits: Arc<AtomicUsize>,
+    expected_hits: Option<usize>,
+}
+
+impl fmt::Debug for Mock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mock")
+            .field("id", &self.id)
+            .field("method", &self.method)
+            .field("path_matcher", &self.path_matcher)
+            .field("query_matcher", &self.query_matcher)
+            .field("headers", &self.headers)
+            .field("body_matcher", &self.body_matcher)
+            .field("response_status", &self.response_status)
+            .field("response_reason", &self.response_reason)
+            .field("response_headers", &self.response_headers)
+            .field("response_body", &self.response_body)
+            .field("response_delay", &self.response_delay)
+            .field("hits", &self.hits.load(Ordering::SeqCst))
+            .field("expected_hits", &self.expected_hits)
+            .finish()
+    }
+}
+
+impl Mock {
+    pub(crate) fn new<P: Into<Matcher>>(method: &str, path: P, state: Arc<Mutex<Vec<Mock>>>) -> Mock {
+        Mock {
+            id: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+            state,
+            method: method.to_owned(),
+            path_matcher: path.into(),
+            query_matcher: None,
+            headers: Vec::new(),
+            body_matcher: None,
+            response_status: 200,
+            response_reason: None,
+            response_headers: Vec::new(),
+            response_body: String::new(),
+            response_delay: None,
+            hits: Arc::new(AtomicUsize::new(0)),
+            expected_hits: None,
+        }
+    }
+
+    /// Restricts the mock to requests carrying the given header. The field
+    /// name is matched case-insensitively.
+    pub fn match_header(mut self, field: &str, value: &str) -> Self {
+        self.headers.push((field.to_lowercase(), value.to_owned()));
+        self
+    }
+
+    /// Restricts the mock to requests whose body satisfies the given
+    /// matcher. Accepts an exact string (`&str`/`String`) or a
+    /// `Matcher::Substring`/`Matcher::Regex` value.
+    ///
+    /// When no body matcher is set, the mock matches regardless of body.
+    pub fn match_body<M: Into<Matcher>>(mut self, matcher: M) -> Self {
+        self.body_matcher = Some(matcher.into());
+        self
+    }
+
+    /// Restricts the mock to requests whose query string satisfies the
+    /// given matcher, e.g. `Matcher::UrlEncoded("page".into(), "2".into())`
+    /// or `Matcher::AllOf(vec![...])` to require several parameters
+    /// regardless of their order.
+    ///
+    /// When no query matcher is set, the mock matches regardless of query
+    /// string.
+    pub fn match_query<M: Into<Matcher>>(mut self, matcher: M) -> Self {
+        self.query_matcher = Some(matcher.into());
+        self
+    }
+
+    /// Sets the status code of the mocked response. Defaults to `200`.
+    ///
+    /// The status line's reason phrase is looked up from the standard IANA
+    /// table (e.g. `200` renders `OK`, `404` renders `Not Found`). Call
+    /// `with_reason` to override it, which is useful for nonstandard codes.
+    pub fn with_status(mut self, status: usize) -> Self {
+        self.response_status = status as u16;
+        self
+    }
+
+    /// Overrides the reason phrase written on the response status line,
+    /// e.g. for a nonstandard status code that has no canonical phrase.
+    pub fn with_reason(mut self, reason: &str) -> Self {
+        self.response_reason = Some(reason.to_owned());
+        self
+    }
+
+    /// Adds a header to the mocked response.
+    pub fn with_header(mut self, field: &str, value: &str) -> Self {
+        self.response_headers.push((field.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Sets the body of the mocked response.
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.response_body = body.to_owned();
+        self
+    }
+
+    /// Delays the mocked response by the given duration before it is
+    /// written, useful for exercising client-side timeout handling.
+    ///
+    /// Other connections are served concurrently while this one sleeps.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.response_delay = Some(delay);
+        self
+    }
+
+    /// Registers the mock so the server starts matching requests against it.
+    pub fn create(&mut self) -> &mut Self {
+        self.state.lock().unwrap().push(self.clone());
+        self
+    }
+
+    /// Registers the mock only for the duration of the closure, then removes
+    /// it.
+    pub fn create_for<F: FnOnce()>(&mut self, f: F) {
+        self.create();
+        f();
+        self.remove();
+    }
+
+    /// Removes the mock from the server.
+    pub fn remove(&mut self) {
+        self.state.lock().unwrap().retain(|mock| mock.id != self.id);
+    }
+
+    /// Declares how many times this mock is expected to be hit. Used by
+    /// `assert()`/`matched()` instead of the default "at least once" check.
+    pub fn expect(mut self, hits: usize) -> Self {
+        self.expected_hits = Some(hits);
+        self
+    }
+
+    /// Returns whether the mock was hit the expected number of times (or at
+    /// least once, if `expect()` was never called).
+    pub fn matched(&self) -> bool {
+        let hits = self.hits.load(Ordering::SeqCst);
+
+        match self.expected_hits {
+            Some(expected) => hits == expected,
+            None => hits > 0,
+        }
+    }
+
+    /// Panics with a descriptive message unless the mock was hit the
+    /// expected number of times (or at least once, if `expect()` was never
+    /// called).
+    pub fn assert(&self) {
+        let hits = self.hits.load(Ordering::SeqCst);
+
+        match self.expected_hits {
+            Some(expected) => assert_eq!(
+                expected, hits,
+                "expected {} {:?} to be hit {} time(s), but it was hit {} time(s)",
+                self.method, self.path_matcher, expected, hits,
+            ),
+            None => assert!(
+                hits > 0,
+                "expected {} {:?} to be hit at least once, but it was never hit",
+                self.method, self.path_matcher,
+            ),
+        }
+    }
+
+    pub(crate) fn record_hit(&self) {
+        self.hits.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub(crate) fn reset_hits(&self) {
+        self.hits.store(0, Ordering::SeqCst);
+    }
+
+    pub(crate) fn matches(
+        &self,
+        method: &str,
+        path: &str,
+        query: &str,
+        headers: &[(String, String)],
+        body: &str,
+    ) -> bool {
+        if self.method != method || !self.path_matcher.matches(path) {
+            return false;
+        }
+
+        if let Some(matcher) = &self.query_matcher {
+            if !matcher.matches(query) {
+                return false;
+            }
+        }
+
+        let headers_match = self.headers.iter().all(|(field, value)| {
+            headers
+                .iter()
+                .any(|(req_field, req_value)| req_field.to_lowercase() == *field && req_value == value)
+        });
+
+        if !headers_match {
+            return false;
+        }
+
+        match &self.body_matcher {
+            Some(matcher) => matcher.matches(body),
+            None => true,
+        }
+    }
+}
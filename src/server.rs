@@ -0,0 +1,284 @@
+use crate::matcher::Matcher;
+use crate::Mock;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// How often the accept loop checks for a shutdown request while there is
+/// no pending connection.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+/// A standalone mock server, started by [`Server::new`].
+///
+/// Each `Server` binds to an OS-assigned ephemeral port and keeps its own
+/// list of registered mocks, so tests that use one don't collide with tests
+/// running in parallel against the global [`crate::mock`]/[`crate::reset`]
+/// functions or against other `Server` instances.
+pub struct Server;
+
+impl Server {
+    /// Binds a new mock server to an OS-assigned ephemeral port and returns
+    /// a guard scoping `mock(...)`/`reset()` calls to it.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> ServerGuard {
+        let state = Arc::new(Mutex::new(Vec::new()));
+        let (address, shutdown) = start_with_shutdown(state.clone());
+
+        ServerGuard { address, state, shutdown }
+    }
+}
+
+/// A handle to a running [`Server`].
+///
+/// Dropping the guard stops its accept loop and clears its registered
+/// mocks, so the listener and its thread don't outlive the test.
+pub struct ServerGuard {
+    address: SocketAddr,
+    state: Arc<Mutex<Vec<Mock>>>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl Drop for ServerGuard {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        self.state.lock().unwrap().clear();
+    }
+}
+
+impl ServerGuard {
+    /// The base URL the server is listening on, e.g. `http://127.0.0.1:52381`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// The address the server is listening on.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Starts a mock scoped to this server instance. See [`crate::mock`].
+    pub fn mock<P: Into<Matcher>>(&self, method: &str, path: P) -> Mock {
+        Mock::new(method, path, self.state.clone())
+    }
+
+    /// Removes all mocks registered on this server and zeroes their hit
+    /// counters.
+    pub fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        for mock in state.iter() {
+            mock.reset_hits();
+        }
+
+        state.clear();
+    }
+}
+
+/// Starts the process-lifetime global server. It never needs to shut down,
+/// so the accept loop blocks on `incoming()` as before, with no polling
+/// overhead on each accepted connection.
+pub(crate) fn start(address: &str, state: Arc<Mutex<Vec<Mock>>>) -> SocketAddr {
+    let listener = TcpListener::bind(address)
+        .unwrap_or_else(|err| panic!("mockito could not bind to {}: {}", address, err));
+    let address = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let state = state.clone();
+            thread::spawn(move || handle(stream, &state));
+        }
+    });
+
+    address
+}
+
+/// Starts an ephemeral per-[`Server`] instance whose accept loop can be
+/// stopped when its [`ServerGuard`] is dropped. The loop polls a
+/// non-blocking listener rather than blocking on `incoming()` so it can
+/// notice the shutdown flag between connections.
+fn start_with_shutdown(state: Arc<Mutex<Vec<Mock>>>) -> (SocketAddr, Arc<AtomicBool>) {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .unwrap_or_else(|err| panic!("mockito could not bind to 127.0.0.1:0: {}", err));
+    let address = listener.local_addr().unwrap();
+    listener
+        .set_nonblocking(true)
+        .unwrap_or_else(|err| panic!("mockito could not configure {}: {}", address, err));
+
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let shutdown_in_loop = shutdown.clone();
+
+    thread::spawn(move || {
+        while !shutdown_in_loop.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let state = state.clone();
+                    thread::spawn(move || handle(stream, &state));
+                }
+                Err(ref err) if err.kind() == ErrorKind::WouldBlock => {
+                    thread::sleep(SHUTDOWN_POLL_INTERVAL);
+                }
+                Err(_) => {}
+            }
+        }
+    });
+
+    (address, shutdown)
+}
+
+fn read_request(stream: &TcpStream) -> Request {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+
+    let mut parts = request_line.trim_end().splitn(3, ' ');
+    let method = parts.next().unwrap_or("").to_owned();
+    let target = parts.next().unwrap_or("");
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_owned(), query.to_owned()),
+        None => (target.to_owned(), String::new()),
+    };
+
+    let mut headers = Vec::new();
+    let mut content_length = 0usize;
+
+    loop {
+        let mut header_line = String::new();
+        reader.read_line(&mut header_line).unwrap();
+
+        if header_line.is_empty() || header_line == "\r\n" {
+            break;
+        }
+
+        if let Some(idx) = header_line.find(':') {
+            let field = header_line[..idx].trim().to_owned();
+            let value = header_line[idx + 1..].trim().to_owned();
+
+            if field.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse().unwrap_or(0);
+            }
+
+            headers.push((field, value));
+        }
+    }
+
+    let mut body = String::new();
+    reader
+        .take(content_length as u64)
+        .read_to_string(&mut body)
+        .unwrap();
+
+    Request { method, path, query, headers, body }
+}
+
+fn handle(stream: TcpStream, state: &Mutex<Vec<Mock>>) {
+    let request = read_request(&stream);
+
+    // Clone the matched mock so the lock is released before any response
+    // delay, letting other connections be matched in the meantime.
+    let matched = {
+        let state = state.lock().unwrap();
+        state
+            .iter()
+            .find(|mock| {
+                mock.matches(
+                    &request.method,
+                    &request.path,
+                    &request.query,
+                    &request.headers,
+                    &request.body,
+                )
+            })
+            .cloned()
+    };
+
+    match matched {
+        Some(mock) => {
+            mock.record_hit();
+            write_response(stream, &mock);
+        }
+        None => write_no_match(stream),
+    }
+}
+
+fn write_response(mut stream: TcpStream, mock: &Mock) {
+    if let Some(delay) = mock.response_delay {
+        thread::sleep(delay);
+    }
+
+    let reason = mock
+        .response_reason
+        .as_deref()
+        .unwrap_or_else(|| reason_phrase(mock.response_status));
+    let mut response = format!("HTTP/1.1 {} {}\r\n", mock.response_status, reason);
+
+    for (field, value) in &mock.response_headers {
+        response.push_str(&format!("{}: {}\r\n", field, value));
+    }
+
+    response.push_str("\r\n");
+    response.push_str(&mock.response_body);
+
+    let _ = stream.write_all(response.as_bytes());
+}
+
+fn write_no_match(mut stream: TcpStream) {
+    let _ = stream.write_all(b"HTTP/1.1 501 Not Implemented\r\n\r\n");
+}
+
+/// Looks up the canonical IANA reason phrase for a status code, falling
+/// back to `"Unknown Status"` for codes outside the standard registry.
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        100 => "Continue",
+        101 => "Switching Protocols",
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        203 => "Non-Authoritative Information",
+        204 => "No Content",
+        205 => "Reset Content",
+        206 => "Partial Content",
+        300 => "Multiple Choices",
+        301 => "Moved Permanently",
+        302 => "Found",
+        303 => "See Other",
+        304 => "Not Modified",
+        307 => "Temporary Redirect",
+        308 => "Permanent Redirect",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        406 => "Not Acceptable",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        410 => "Gone",
+        411 => "Length Required",
+        412 => "Precondition Failed",
+        413 => "Payload Too Large",
+        414 => "URI Too Long",
+        415 => "Unsupported Media Type",
+        418 => "I'm a Teapot",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Unknown Status",
+    }
+}
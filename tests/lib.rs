@@ -1,17 +1,33 @@
 extern crate mockito;
+extern crate regex;
 
-use std::net::TcpStream;
+use std::net::{SocketAddr, TcpStream};
 use std::io::{Read, Write, BufRead, BufReader};
-use mockito::{SERVER_ADDRESS, mock, reset};
+use std::thread;
+use std::time::{Duration, Instant};
+use mockito::{SERVER_ADDRESS, mock, reset, Matcher, Server};
+use regex::Regex;
 
 fn request_stream(route: &str, headers: &str) -> TcpStream {
-    let mut stream = TcpStream::connect(SERVER_ADDRESS).unwrap();
+    request_stream_to(SERVER_ADDRESS, route, headers)
+}
+
+fn request_stream_to(address: &str, route: &str, headers: &str) -> TcpStream {
+    let mut stream = TcpStream::connect(address).unwrap();
     let message = [route, " HTTP/1.1\r\n", headers, "\r\n"].join("");
     stream.write_all(message.as_bytes()).unwrap();
 
     stream
 }
 
+fn request_with_body(route: &str, headers: &str, body: &str) -> TcpStream {
+    let mut stream = TcpStream::connect(SERVER_ADDRESS).unwrap();
+    let message = [route, " HTTP/1.1\r\n", headers, "\r\n", body].join("");
+    stream.write_all(message.as_bytes()).unwrap();
+
+    stream
+}
+
 fn parse_stream(stream: TcpStream, content_length: usize) -> (String, Vec<String>, String) {
     let mut reader = BufReader::new(stream);
 
@@ -37,6 +53,27 @@ fn request(route: &str, headers: &str, expected_content_length: usize) -> (Strin
     parse_stream(request_stream(route, headers), expected_content_length)
 }
 
+fn request_to(
+    address: SocketAddr,
+    route: &str,
+    headers: &str,
+    expected_content_length: usize,
+) -> (String, Vec<String>, String) {
+    parse_stream(
+        request_stream_to(&address.to_string(), route, headers),
+        expected_content_length,
+    )
+}
+
+fn request_with_req_body(
+    route: &str,
+    headers: &str,
+    body: &str,
+    expected_content_length: usize,
+) -> (String, Vec<String>, String) {
+    parse_stream(request_with_body(route, headers, body), expected_content_length)
+}
+
 #[test]
 fn test_create_starts_the_server() {
     mock("GET", "/").with_body("hello").create();
@@ -53,7 +90,7 @@ fn test_simple_route_mock() {
     mock("GET", "/hello").with_body(mocked_body).create();
 
     let (status_line, _, body) = request("GET /hello", "", 5);
-    assert_eq!("HTTP/1.1 200 <unknown status code>\r\n", status_line);
+    assert_eq!("HTTP/1.1 200 OK\r\n", status_line);
     assert_eq!(mocked_body, body);
 }
 
@@ -71,6 +108,50 @@ fn test_two_route_mocks() {
     assert_eq!("bbb", body_b);
 }
 
+#[test]
+fn test_expect_and_assert_pass_when_hit_count_matches() {
+    reset();
+
+    let mut mock = mock("GET", "/a").expect(2);
+    mock.create();
+
+    assert!(!mock.matched());
+
+    request("GET /a", "", 0);
+    request("GET /a", "", 0);
+
+    assert!(mock.matched());
+    mock.assert();
+}
+
+#[test]
+#[should_panic(expected = "expected GET")]
+fn test_assert_panics_when_hit_count_does_not_match() {
+    reset();
+
+    let mut mock = mock("GET", "/a").expect(2);
+    mock.create();
+
+    request("GET /a", "", 0);
+
+    mock.assert();
+}
+
+#[test]
+fn test_reset_zeroes_hit_counts() {
+    reset();
+
+    let mock = mock("GET", "/a").create().clone();
+    request("GET /a", "", 0);
+    assert!(mock.matched());
+
+    reset();
+
+    let mut other_mock = mock.clone();
+    other_mock.create();
+    assert!(!other_mock.matched());
+}
+
 #[test]
 fn test_no_match_returns_501() {
     reset();
@@ -81,6 +162,43 @@ fn test_no_match_returns_501() {
     assert_eq!("HTTP/1.1 501 Not Implemented\r\n", status_line);
 }
 
+#[test]
+fn test_path_matches_regex() {
+    reset();
+
+    mock("GET", Matcher::Regex(Regex::new(r"^/users/\d+$").unwrap()))
+        .with_body("a user")
+        .create();
+
+    let (_, _, body_a) = request("GET /users/123", "", 6);
+    assert_eq!("a user", body_a);
+
+    let (_, _, body_b) = request("GET /users/456", "", 6);
+    assert_eq!("a user", body_b);
+
+    let (status_line, _, _) = request("GET /users/abc", "", 0);
+    assert_eq!("HTTP/1.1 501 Not Implemented\r\n", status_line);
+}
+
+#[test]
+fn test_match_query() {
+    reset();
+
+    mock("GET", "/search")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("page".to_string(), "2".to_string()),
+            Matcher::UrlEncoded("sort".to_string(), "asc".to_string()),
+        ]))
+        .with_body("matched query")
+        .create();
+
+    let (_, _, body) = request("GET /search?sort=asc&page=2", "", 13);
+    assert_eq!("matched query", body);
+
+    let (status_line, _, _) = request("GET /search?page=3&sort=asc", "", 0);
+    assert_eq!("HTTP/1.1 501 Not Implemented\r\n", status_line);
+}
+
 #[test]
 fn test_match_header() {
     reset();
@@ -102,6 +220,65 @@ fn test_match_header() {
     assert_eq!("hello", body_text);
 }
 
+#[test]
+fn test_match_body_exact() {
+    reset();
+
+    mock("POST", "/")
+        .match_body("hello")
+        .with_body("matched exact")
+        .create();
+
+    let headers = "Content-Length: 5\r\n";
+    let (_, _, body) = request_with_req_body("POST /", headers, "hello", 13);
+    assert_eq!("matched exact", body);
+
+    let (status_line, _, _) = request_with_req_body("POST /", headers, "howdy", 0);
+    assert_eq!("HTTP/1.1 501 Not Implemented\r\n", status_line);
+}
+
+#[test]
+fn test_match_body_substring() {
+    reset();
+
+    mock("POST", "/")
+        .match_body(Matcher::Substring("needle".to_string()))
+        .with_body("matched substring")
+        .create();
+
+    let headers = "Content-Length: 22\r\n";
+    let (_, _, body) = request_with_req_body("POST /", headers, "a needle in a haystack", 17);
+    assert_eq!("matched substring", body);
+}
+
+#[test]
+fn test_match_body_regex() {
+    reset();
+
+    mock("POST", "/")
+        .match_body(Matcher::Regex(Regex::new(r"^\d+$").unwrap()))
+        .with_body("matched regex")
+        .create();
+
+    let headers = "Content-Length: 3\r\n";
+    let (_, _, body) = request_with_req_body("POST /", headers, "123", 13);
+    assert_eq!("matched regex", body);
+
+    let (status_line, _, _) = request_with_req_body("POST /", headers, "abc", 0);
+    assert_eq!("HTTP/1.1 501 Not Implemented\r\n", status_line);
+}
+
+#[test]
+fn test_match_body_defaults_to_matching_any_body() {
+    reset();
+
+    mock("POST", "/").with_body("matched").create();
+
+    let headers = "Content-Length: 4\r\n";
+    let (_, _, body) = request_with_req_body("POST /", headers, "ping", 7);
+    assert_eq!("matched", body);
+}
+
 #[test]
 fn test_match_header_is_case_insensitive_on_the_field_name() {
     reset();
@@ -109,10 +286,10 @@ fn test_match_header_is_case_insensitive_on_the_field_name() {
     mock("GET", "/").match_header("content-type", "text/plain").create();
 
     let (uppercase_status_line, _, _) = request("GET /", "Content-Type: text/plain\r\n", 0);
-    assert_eq!("HTTP/1.1 200 <unknown status code>\r\n", uppercase_status_line);
+    assert_eq!("HTTP/1.1 200 OK\r\n", uppercase_status_line);
 
     let (lowercase_status_line, _, _) = request("GET /", "content-type: text/plain\r\n", 0);
-    assert_eq!("HTTP/1.1 200 <unknown status code>\r\n", lowercase_status_line);
+    assert_eq!("HTTP/1.1 200 OK\r\n", lowercase_status_line);
 }
 
 #[test]
@@ -142,7 +319,21 @@ fn test_mock_with_status() {
         .create();
 
     let (status_line, _, _) = request("GET /", "", 0);
-    assert_eq!("HTTP/1.1 204 <unknown status code>\r\n", status_line);
+    assert_eq!("HTTP/1.1 204 No Content\r\n", status_line);
+}
+
+#[test]
+fn test_mock_with_reason_overrides_nonstandard_status() {
+    reset();
+
+    mock("GET", "/")
+        .with_status(999)
+        .with_reason("Custom Status")
+        .with_body("")
+        .create();
+
+    let (status_line, _, _) = request("GET /", "", 0);
+    assert_eq!("HTTP/1.1 999 Custom Status\r\n", status_line);
 }
 
 #[test]
@@ -180,7 +371,7 @@ fn test_reset_clears_mocks() {
     mock("GET", "/reset").create();
 
     let (working_status_line, _, _) = request("GET /reset", "", 0);
-    assert_eq!("HTTP/1.1 200 <unknown status code>\r\n", working_status_line);
+    assert_eq!("HTTP/1.1 200 OK\r\n", working_status_line);
 
     reset();
 
@@ -196,7 +387,7 @@ fn test_mock_remove_clears_the_mock() {
     mock.create();
 
     let (working_status_line, _, _) = request("GET /", "", 0);
-    assert_eq!("HTTP/1.1 200 <unknown status code>\r\n", working_status_line);
+    assert_eq!("HTTP/1.1 200 OK\r\n", working_status_line);
 
     mock.remove();
 
@@ -210,9 +401,139 @@ fn test_mock_create_for_is_only_available_during_the_closure_lifetime() {
 
     mock("GET", "/").create_for( || {
         let (working_status_line, _, _) = request("GET /", "", 0);
-        assert_eq!("HTTP/1.1 200 <unknown status code>\r\n", working_status_line);
+        assert_eq!("HTTP/1.1 200 OK\r\n", working_status_line);
     });
 
     let (reset_status_line, _, _) = request("GET /", "", 0);
     assert_eq!("HTTP/1.1 501 Not Implemented\r\n", reset_status_line);
+}
+
+#[test]
+fn test_server_is_isolated_from_the_global_server() {
+    reset();
+
+    let server = Server::new();
+    server.mock("GET", "/hello").with_body("isolated").create();
+
+    let (status_line, _, body) = request_to(server.address(), "GET /hello", "", 8);
+    assert_eq!("HTTP/1.1 200 OK\r\n", status_line);
+    assert_eq!("isolated", body);
+
+    let (global_status_line, _, _) = request("GET /hello", "", 0);
+    assert_eq!("HTTP/1.1 501 Not Implemented\r\n", global_status_line);
+}
+
+#[test]
+fn test_server_url_matches_its_address() {
+    let server = Server::new();
+    assert_eq!(format!("http://{}", server.address()), server.url());
+}
+
+#[test]
+fn test_two_servers_do_not_share_mocks() {
+    let server_a = Server::new();
+    let server_b = Server::new();
+
+    server_a.mock("GET", "/").with_body("a").create();
+    server_b.mock("GET", "/").with_body("b").create();
+
+    let (_, _, body_a) = request_to(server_a.address(), "GET /", "", 1);
+    assert_eq!("a", body_a);
+
+    let (_, _, body_b) = request_to(server_b.address(), "GET /", "", 1);
+    assert_eq!("b", body_b);
+}
+
+#[test]
+fn test_server_reset_clears_its_own_mocks() {
+    let server = Server::new();
+    server.mock("GET", "/").with_body("hi").create();
+
+    let (working_status_line, _, _) = request_to(server.address(), "GET /", "", 2);
+    assert_eq!("HTTP/1.1 200 OK\r\n", working_status_line);
+
+    server.reset();
+
+    let (reset_status_line, _, _) = request_to(server.address(), "GET /", "", 0);
+    assert_eq!("HTTP/1.1 501 Not Implemented\r\n", reset_status_line);
+}
+
+#[test]
+fn test_with_delay_waits_before_responding() {
+    let server = Server::new();
+    server
+        .mock("GET", "/slow")
+        .with_delay(Duration::from_millis(200))
+        .with_body("done")
+        .create();
+
+    let started = Instant::now();
+    let (_, _, body) = request_to(server.address(), "GET /slow", "", 4);
+
+    assert!(started.elapsed() >= Duration::from_millis(200));
+    assert_eq!("done", body);
+}
+
+#[test]
+fn test_with_delay_does_not_block_other_connections() {
+    let server = Server::new();
+    server
+        .mock("GET", "/slow")
+        .with_delay(Duration::from_millis(1000))
+        .with_body("slow")
+        .create();
+    server.mock("GET", "/fast").with_body("fast").create();
+
+    let address = server.address();
+    let slow = thread::spawn(move || request_to(address, "GET /slow", "", 4));
+
+    let started = Instant::now();
+    let (_, _, fast_body) = request_to(address, "GET /fast", "", 4);
+    assert!(started.elapsed() < Duration::from_millis(500));
+    assert_eq!("fast", fast_body);
+
+    let (_, _, slow_body) = slow.join().unwrap();
+    assert_eq!("slow", slow_body);
+}
+
+#[test]
+fn test_dropping_server_guard_stops_the_listener() {
+    let server = Server::new();
+    let address = server.address();
+
+    server.mock("GET", "/").with_body("hi").create();
+    let (status_line, _, _) = request_to(address, "GET /", "", 2);
+    assert_eq!("HTTP/1.1 200 OK\r\n", status_line);
+
+    drop(server);
+    thread::sleep(Duration::from_millis(100));
+
+    assert!(TcpStream::connect(address).is_err());
+}
+
+/// Like the other `Server`-only tests in this file, this one touches neither
+/// the global `mock`/`reset` functions nor `SERVER_ADDRESS`, so it needs no
+/// `reset()` and does not depend on `--test-threads=1`: each `Server` is
+/// its own isolated instance, so driving several of them at once — as
+/// separate `#[test]` functions running in parallel effectively would —
+/// produces no cross-talk.
+#[test]
+fn test_servers_run_concurrently_without_reset() {
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            thread::spawn(move || {
+                let server = Server::new();
+                let body = format!("body-{}", i);
+
+                server.mock("GET", "/").with_body(&body).create();
+
+                let (_, _, response_body) = request_to(server.address(), "GET /", "", body.len());
+                assert_eq!(body, response_body);
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
 }
\ No newline at end of file